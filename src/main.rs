@@ -1,33 +1,293 @@
-use std::os::unix::fs::PermissionsExt;
+use std::os::unix::fs::{FileTypeExt, MetadataExt, PermissionsExt};
 use std::time::{SystemTime, UNIX_EPOCH};
 use chrono::{DateTime, Utc};
 use tabwriter::TabWriter;
 use std::io::Write;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::{env, fs};
+use clap::Parser;
+use users::{get_group_by_gid, get_user_by_uid};
+use rayon::prelude::*;
+use std::io::Read;
+use std::time::Duration;
+use flate2::read::GzDecoder;
+use tar::Archive;
 
+/// A small, friendly replacement for `ls`.
+#[derive(Parser, Debug)]
+#[command(name = "custom-ls", about = "List directory contents")]
+struct Cli {
+    /// Directory to list
+    #[arg(default_value = ".")]
+    path: String,
+
+    /// Sort by file size, largest first
+    #[arg(short = 'S')]
+    sort_size: bool,
+
+    /// Sort by modification time, newest first
+    #[arg(short = 't')]
+    sort_time: bool,
+
+    /// Do not sort; list entries in directory order
+    #[arg(short = 'U')]
+    no_sort: bool,
+
+    /// Reverse the order of the sort
+    #[arg(short = 'r')]
+    reverse: bool,
+
+    /// Recursively list subdirectories
+    #[arg(short = 'R')]
+    recursive: bool,
+
+    /// Control whether output is colorized: auto, always, or never
+    #[arg(long = "color", default_value = "auto")]
+    color: String,
+
+    /// Use a long listing format with permissions, owner, and group
+    #[arg(short = 'l')]
+    long: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortBy {
+    Name,
+    Size,
+    Time,
+    None,
+}
+
+impl SortBy {
+    fn from_cli(cli: &Cli) -> SortBy {
+        if cli.no_sort {
+            SortBy::None
+        } else if cli.sort_time {
+            SortBy::Time
+        } else if cli.sort_size {
+            SortBy::Size
+        } else {
+            SortBy::Name
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    fn from_flag(flag: &str) -> ColorMode {
+        match flag {
+            "always" => ColorMode::Always,
+            "never" => ColorMode::Never,
+            _ => ColorMode::Auto,
+        }
+    }
+}
+
+/// Resolved coloring behavior: whether to emit ANSI codes at all, and the
+/// `LS_COLORS` lookup table (file-kind keys like `di`/`ex`/`fi`, plus `*.ext` keys).
+struct ColorConfig {
+    enabled: bool,
+    codes: HashMap<String, String>,
+}
+
+impl ColorConfig {
+    fn resolve(mode: ColorMode) -> ColorConfig {
+        let enabled = match mode {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => env::var_os("NO_COLOR").is_none() && stdout_is_tty(),
+        };
+        ColorConfig {
+            enabled,
+            codes: parse_ls_colors(),
+        }
+    }
+
+    /// Wraps `name` in the SGR code for `kind_key` (e.g. `"di"`, `"ex"`, `"fi"`),
+    /// falling back to an extension match (`*.rs`) when the kind itself has no entry.
+    fn paint(&self, name: &str, kind_key: &str) -> String {
+        if !self.enabled {
+            return name.to_string();
+        }
+        let code = self.codes.get(kind_key).or_else(|| {
+            name.rsplit_once('.')
+                .and_then(|(_, ext)| self.codes.get(&format!("*.{}", ext)))
+        });
+        match code {
+            Some(code) => format!("\x1b[{}m{}\x1b[0m", code, name),
+            None => name.to_string(),
+        }
+    }
+}
+
+/// Bundles the per-invocation settings that affect how a listing is rendered,
+/// so `print`/`print_tree`/`print_block` don't have to grow a parameter per flag.
+struct PrintOptions {
+    colors: ColorConfig,
+    long: bool,
+}
+
+fn stdout_is_tty() -> bool {
+    unsafe { libc::isatty(libc::STDOUT_FILENO) != 0 }
+}
+
+/// Parses `LS_COLORS`-style `key=value` pairs separated by `:`, e.g. `di=01;34:*.rs=01;31`.
+fn parse_ls_colors() -> HashMap<String, String> {
+    let mut codes = HashMap::new();
+    if let Ok(raw) = env::var("LS_COLORS") {
+        for entry in raw.split(':') {
+            if let Some((key, value)) = entry.split_once('=') {
+                if !key.is_empty() && !value.is_empty() {
+                    codes.insert(key.to_string(), value.to_string());
+                }
+            }
+        }
+    }
+    codes
+}
 
 struct DirContents {
     files: Vec<FileInfo>,
     directories: Vec<FileInfo>,
-    executables: Vec<FileInfo>
+    executables: Vec<FileInfo>,
+    symlinks: Vec<FileInfo>,
+    block_devices: Vec<FileInfo>,
+    char_devices: Vec<FileInfo>,
+    sockets: Vec<FileInfo>,
+    fifos: Vec<FileInfo>,
+    /// Subdirectories, populated only in `-R` mode, in listing order.
+    children: Vec<(String, DirContents)>,
+    /// Whether this tree was built in `-R` mode; controls whether `print` emits headers.
+    recursive: bool,
 }
 struct FileInfo {
     name: String,
     readable_size: String,
-    modified_at: String
+    modified_at: String,
+    size: u64,
+    modified: SystemTime,
+    permissions: String,
+    owner: String,
+    group: String,
+    /// Set only for `FileKind::Symlink` entries; the link's target, for `name -> target`.
+    symlink_target: Option<String>,
 }
-fn main() {
-    // Get command-line arguments
-    let args: Vec<String> = env::args().collect();
 
-    // Determine the directory to use
-    let dir_path = if args.len() > 1 {
-        &args[1]  // Use provided path
-    } else {
-        "."  // Default to current directory
+/// The kind of filesystem entry, derived from `std::os::unix::fs::FileTypeExt` plus the
+/// executable bit. `symlink_metadata` (not `metadata`) must back this classification so
+/// symlinks are reported as themselves rather than whatever they point to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileKind {
+    Directory,
+    NormalFile,
+    Executable,
+    Symlink,
+    BlockDevice,
+    CharDevice,
+    Socket,
+    Fifo,
+}
+
+impl FileKind {
+    fn classify(file_type: &fs::FileType, mode: u32) -> FileKind {
+        if file_type.is_symlink() {
+            FileKind::Symlink
+        } else if file_type.is_dir() {
+            FileKind::Directory
+        } else if file_type.is_block_device() {
+            FileKind::BlockDevice
+        } else if file_type.is_char_device() {
+            FileKind::CharDevice
+        } else if file_type.is_socket() {
+            FileKind::Socket
+        } else if file_type.is_fifo() {
+            FileKind::Fifo
+        } else if mode & 0o111 != 0 {
+            FileKind::Executable
+        } else {
+            FileKind::NormalFile
+        }
+    }
+
+    /// The leading character of an `ls -l`-style permission string, e.g. `d` for a directory.
+    fn type_char(&self) -> char {
+        match self {
+            FileKind::Directory => 'd',
+            FileKind::Symlink => 'l',
+            FileKind::BlockDevice => 'b',
+            FileKind::CharDevice => 'c',
+            FileKind::Socket => 's',
+            FileKind::Fifo => 'p',
+            FileKind::NormalFile | FileKind::Executable => '-',
+        }
+    }
+}
+
+/// Renders the permission bits of `mode` as an `ls -l`-style string, e.g. `drwxr-xr-x`.
+/// `type_char` is the leading type indicator (`d` for directories, `-` for regular files).
+fn format_permissions(type_char: char, mode: u32) -> String {
+    let mut perms = String::with_capacity(10);
+    perms.push(type_char);
+    perms.push(if mode & 0o400 != 0 { 'r' } else { '-' });
+    perms.push(if mode & 0o200 != 0 { 'w' } else { '-' });
+    perms.push(special_exec_char(mode & 0o100 != 0, mode & 0o4000 != 0, 's'));
+    perms.push(if mode & 0o040 != 0 { 'r' } else { '-' });
+    perms.push(if mode & 0o020 != 0 { 'w' } else { '-' });
+    perms.push(special_exec_char(mode & 0o010 != 0, mode & 0o2000 != 0, 's'));
+    perms.push(if mode & 0o004 != 0 { 'r' } else { '-' });
+    perms.push(if mode & 0o002 != 0 { 'w' } else { '-' });
+    perms.push(special_exec_char(mode & 0o001 != 0, mode & 0o1000 != 0, 't'));
+    perms
+}
+
+/// Picks the exec-position character: lowercase `special_char` when both the exec bit
+/// and the setuid/setgid/sticky bit are set, uppercase when only the special bit is set,
+/// `x`/`-` otherwise.
+fn special_exec_char(exec: bool, special: bool, special_char: char) -> char {
+    match (exec, special) {
+        (true, true) => special_char,
+        (false, true) => special_char.to_ascii_uppercase(),
+        (true, false) => 'x',
+        (false, false) => '-',
+    }
+}
+
+impl FileInfo {
+    /// The text shown in the Name column: `name -> target` for symlinks, else just `name`.
+    fn display_name(&self) -> String {
+        match &self.symlink_target {
+            Some(target) => format!("{} -> {}", self.name, target),
+            None => self.name.clone(),
+        }
+    }
+}
+
+fn owner_name(uid: u32) -> String {
+    get_user_by_uid(uid)
+        .map(|user| user.name().to_string_lossy().into_owned())
+        .unwrap_or_else(|| uid.to_string())
+}
+
+fn group_name(gid: u32) -> String {
+    get_group_by_gid(gid)
+        .map(|group| group.name().to_string_lossy().into_owned())
+        .unwrap_or_else(|| gid.to_string())
+}
+fn main() {
+    let cli = Cli::parse();
+    let sort_by = SortBy::from_cli(&cli);
+    let print_options = PrintOptions {
+        colors: ColorConfig::resolve(ColorMode::from_flag(&cli.color)),
+        long: cli.long,
     };
+    let dir_path = &cli.path;
 
     // Resolve the absolute path
     let full_path = Path::new(dir_path).canonicalize();
@@ -35,8 +295,13 @@ fn main() {
     match full_path {
         Ok(path) => {
             if path.is_dir() {
-                let dir_contents = extract_files_from_path(dir_path);
-                dir_contents.print();
+                let mut dir_contents = build_tree(dir_path, cli.recursive);
+                dir_contents.sort(sort_by, cli.reverse);
+                dir_contents.print(&print_options);
+            } else if is_tar_path(&path) {
+                let mut dir_contents = extract_tar_contents(&path);
+                dir_contents.sort(sort_by, cli.reverse);
+                dir_contents.print(&print_options);
             } else {
                 eprintln!("❌ Error: '{}' is not a directory.", dir_path);
             }
@@ -47,93 +312,459 @@ fn main() {
     }
 }
 
-fn extract_files_from_path(path: &str) -> DirContents {
+/// Classifies a tar member into the same `FileKind` buckets as a real directory entry,
+/// from just its header's directory flag and mode bits (tar has no device/socket/fifo/
+/// symlink buckets here, so those fold into `NormalFile`/`Executable`/`Directory`).
+fn classify_tar_entry(is_dir: bool, mode: u32) -> FileKind {
+    if is_dir {
+        FileKind::Directory
+    } else if mode & 0o111 != 0 {
+        FileKind::Executable
+    } else {
+        FileKind::NormalFile
+    }
+}
+
+/// Whether `path` looks like a `.tar` or `.tar.gz`/`.tgz` archive, so it can be browsed
+/// like a directory instead of rejected as "not a directory".
+fn is_tar_path(path: &Path) -> bool {
+    let name = path.to_string_lossy().to_lowercase();
+    name.ends_with(".tar") || name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+/// Lists a `.tar`/`.tar.gz` archive's members as if it were a directory, reusing the
+/// same `DirContents` buckets and `FileInfo` fields (and therefore the same emoji,
+/// human-readable size, and TabWriter formatting) as a real directory listing.
+fn extract_tar_contents(path: &Path) -> DirContents {
     let mut files: Vec<FileInfo> = Vec::new();
     let mut directories: Vec<FileInfo> = Vec::new();
     let mut executables: Vec<FileInfo> = Vec::new();
 
-    // Read directory entries
-    if let Ok(entries) = fs::read_dir(path) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let metadata = entry.metadata().unwrap();
-                let file_type = metadata.file_type();
-                let file_name = entry.file_name().into_string().unwrap();
-                
-                // Skip "." and ".."
-                if file_name == "." || file_name == ".." {
+    if let Ok(file) = fs::File::open(path) {
+        let name_lower = path.to_string_lossy().to_lowercase();
+        let reader: Box<dyn Read> = if name_lower.ends_with(".gz") || name_lower.ends_with(".tgz") {
+            Box::new(GzDecoder::new(file))
+        } else {
+            Box::new(file)
+        };
+
+        let mut archive = Archive::new(reader);
+        if let Ok(entries) = archive.entries() {
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let header = entry.header();
+
+                let entry_path = match entry.path() {
+                    Ok(entry_path) => entry_path.into_owned(),
+                    Err(_) => continue,
+                };
+                let name = entry_path.to_string_lossy().trim_end_matches('/').to_string();
+                if name.is_empty() {
                     continue;
                 }
 
-                // Get file size in human-readable format
-                let file_size = human_readable_size(metadata.len());
-
-                // Get last modification time
-                if let Ok(modified) = metadata.modified() {
-                    let duration = modified.duration_since(UNIX_EPOCH).unwrap();
-                    let datetime: DateTime<Utc> = DateTime::<Utc>::from(SystemTime::UNIX_EPOCH + duration);
-                    let mod_time = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
-
-                    // Categorize items
-                    if file_type.is_dir() {
-                        directories.push(FileInfo {
-                            name: file_name,
-                            readable_size: file_size,
-                            modified_at: mod_time
-                        })
-                    } else if file_type.is_file() {
-                        if metadata.permissions().mode() & 0o111 != 0 {
-                            executables.push(FileInfo {
-                                name: file_name,
-                                readable_size: file_size,
-                                modified_at: mod_time
-                            })
-                        } else {
-                            files.push(FileInfo { 
-                                name: file_name, 
-                                readable_size: file_size, 
-                                modified_at: mod_time 
-                            });
-                        }
-                    }
+                let size = header.size().unwrap_or(0);
+                let mode = header.mode().unwrap_or(0);
+                let mtime = header.mtime().unwrap_or(0);
+                // GNU-format extended headers can encode an arbitrary 64-bit mtime via
+                // base-256 fields, well past what `SystemTime`'s `Add` can represent
+                // without panicking; fall back to the epoch for an out-of-range value
+                // rather than let one crafted/corrupted archive crash the listing.
+                let modified = UNIX_EPOCH
+                    .checked_add(Duration::from_secs(mtime))
+                    .unwrap_or(UNIX_EPOCH);
+                let mod_time = DateTime::<Utc>::from(modified).format("%Y-%m-%d %H:%M:%S").to_string();
+
+                let kind = classify_tar_entry(header.entry_type().is_dir(), mode);
+
+                let owner = header
+                    .username()
+                    .ok()
+                    .flatten()
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| header.uid().unwrap_or(0).to_string());
+                let group = header
+                    .groupname()
+                    .ok()
+                    .flatten()
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| header.gid().unwrap_or(0).to_string());
+
+                let file_info = FileInfo {
+                    name,
+                    readable_size: human_readable_size(size),
+                    modified_at: mod_time,
+                    size,
+                    modified,
+                    permissions: format_permissions(kind.type_char(), mode),
+                    owner,
+                    group,
+                    symlink_target: None,
+                };
+
+                match kind {
+                    FileKind::Directory => directories.push(file_info),
+                    FileKind::Executable => executables.push(file_info),
+                    _ => files.push(file_info),
                 }
             }
         }
     }
-    files.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    directories.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    executables.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+    DirContents {
+        files,
+        directories,
+        executables,
+        symlinks: Vec::new(),
+        block_devices: Vec::new(),
+        char_devices: Vec::new(),
+        sockets: Vec::new(),
+        fifos: Vec::new(),
+        children: Vec::new(),
+        recursive: false,
+    }
+}
+
+/// Classifies the single directory entry at `entry_path` via an `lstat`. Returns `None`
+/// for entries that vanish, are unreadable, or are `.`/`..`, so callers can `filter_map` it.
+fn classify_entry(entry_path: &Path) -> Option<(FileKind, FileInfo)> {
+    let file_name = entry_path.file_name()?.to_str()?.to_string();
+    if file_name == "." || file_name == ".." {
+        return None;
+    }
+
+    // symlink_metadata (lstat), not metadata, so links are reported as themselves
+    // instead of silently following through to their target.
+    let metadata = fs::symlink_metadata(entry_path).ok()?;
+    let file_type = metadata.file_type();
+    let kind = FileKind::classify(&file_type, metadata.permissions().mode());
+
+    let modified = metadata.modified().ok()?;
+    let duration = modified.duration_since(UNIX_EPOCH).ok()?;
+    let datetime: DateTime<Utc> = DateTime::<Utc>::from(SystemTime::UNIX_EPOCH + duration);
+    let mod_time = datetime.format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let symlink_target = if kind == FileKind::Symlink {
+        fs::read_link(entry_path).ok().map(|target| target.display().to_string())
+    } else {
+        None
+    };
+
+    Some((
+        kind,
+        FileInfo {
+            name: file_name,
+            readable_size: human_readable_size(metadata.len()),
+            modified_at: mod_time,
+            size: metadata.len(),
+            modified,
+            permissions: format_permissions(kind.type_char(), metadata.permissions().mode()),
+            owner: owner_name(metadata.uid()),
+            group: group_name(metadata.gid()),
+            symlink_target,
+        },
+    ))
+}
+
+/// Lists `path`'s entries and builds their `FileInfo`s in parallel with rayon, since each
+/// one costs a blocking `lstat` syscall and large directories make that the bottleneck.
+/// `par_iter().filter_map().collect()` on a `Vec` preserves the original directory order
+/// regardless of which entry finishes its syscall first, so `-U` stays deterministic.
+fn extract_files_from_path(path: &str) -> DirContents {
+    let entry_paths: Vec<PathBuf> = match fs::read_dir(path) {
+        Ok(entries) => entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect(),
+        Err(_) => Vec::new(),
+    };
+
+    let classified: Vec<(FileKind, FileInfo)> = entry_paths
+        .par_iter()
+        .filter_map(|entry_path| classify_entry(entry_path))
+        .collect();
+
+    let mut files: Vec<FileInfo> = Vec::new();
+    let mut directories: Vec<FileInfo> = Vec::new();
+    let mut executables: Vec<FileInfo> = Vec::new();
+    let mut symlinks: Vec<FileInfo> = Vec::new();
+    let mut block_devices: Vec<FileInfo> = Vec::new();
+    let mut char_devices: Vec<FileInfo> = Vec::new();
+    let mut sockets: Vec<FileInfo> = Vec::new();
+    let mut fifos: Vec<FileInfo> = Vec::new();
+
+    for (kind, file_info) in classified {
+        match kind {
+            FileKind::Directory => directories.push(file_info),
+            FileKind::NormalFile => files.push(file_info),
+            FileKind::Executable => executables.push(file_info),
+            FileKind::Symlink => symlinks.push(file_info),
+            FileKind::BlockDevice => block_devices.push(file_info),
+            FileKind::CharDevice => char_devices.push(file_info),
+            FileKind::Socket => sockets.push(file_info),
+            FileKind::Fifo => fifos.push(file_info),
+        }
+    }
+
     DirContents {
         files,
         directories,
-        executables
+        executables,
+        symlinks,
+        block_devices,
+        char_devices,
+        sockets,
+        fifos,
+        children: Vec::new(),
+        recursive: false,
     }
 }
 
+/// One in-progress directory on the explicit work-stack used by `build_tree`.
+struct TreeFrame {
+    name: String,
+    contents: DirContents,
+    /// Subdirectories still to be visited, in listing order (popped from the back).
+    pending: Vec<(String, PathBuf)>,
+    /// This directory's canonical path, dropped from the ancestor set once the frame is done.
+    canonical: Option<PathBuf>,
+}
+
+/// Builds the full (or single-level) directory tree rooted at `path`.
+///
+/// When `recursive` is set, every subdirectory is visited via an explicit stack of
+/// `TreeFrame`s rather than function recursion, so pathological trees can't blow the
+/// stack. Since `FileKind::classify` (chunk0-5) buckets symlinked directories as
+/// `FileKind::Symlink` rather than `FileKind::Directory`, `-R` also descends into any
+/// symlink in `contents.symlinks` whose target is a directory; the canonical paths of the
+/// directories currently being descended are tracked in a `HashSet`, so a symlink loop
+/// back into an ancestor is never queued.
+fn build_tree(path: &str, recursive: bool) -> DirContents {
+    let root_path = Path::new(path);
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+
+    fn make_frame(name: String, path: &Path, recursive: bool, visited: &mut HashSet<PathBuf>) -> TreeFrame {
+        let mut contents = extract_files_from_path(path.to_str().unwrap_or(""));
+        contents.recursive = recursive;
+        let canonical = path.canonicalize().ok();
+        if let Some(canon) = &canonical {
+            visited.insert(canon.clone());
+        }
+
+        let mut pending = Vec::new();
+        if recursive {
+            let subdir_names = contents
+                .directories
+                .iter()
+                .map(|dir| dir.name.clone())
+                .chain(
+                    contents
+                        .symlinks
+                        .iter()
+                        .filter(|symlink| path.join(&symlink.name).is_dir())
+                        .map(|symlink| symlink.name.clone()),
+                );
+
+            for name in subdir_names {
+                let child_path = path.join(&name);
+                let is_new = match child_path.canonicalize() {
+                    Ok(canon) => !visited.contains(&canon),
+                    Err(_) => true,
+                };
+                if is_new {
+                    pending.push((name, child_path));
+                }
+            }
+            pending.reverse(); // so `pop()` below visits them in listing order
+        }
+
+        TreeFrame { name, contents, pending, canonical }
+    }
+
+    let mut stack: Vec<TreeFrame> = vec![make_frame(String::new(), root_path, recursive, &mut visited)];
+
+    loop {
+        if let Some((child_name, child_path)) = stack.last_mut().unwrap().pending.pop() {
+            stack.push(make_frame(child_name, &child_path, recursive, &mut visited));
+            continue;
+        }
+
+        let finished = stack.pop().unwrap();
+        if let Some(canon) = &finished.canonical {
+            visited.remove(canon);
+        }
+        match stack.last_mut() {
+            Some(parent) => parent.contents.children.push((finished.name, finished.contents)),
+            None => return finished.contents,
+        }
+    }
+}
+
+/// A boxed `FileInfo` comparator, named so `DirContents::sort` doesn't inline the
+/// full `Box<dyn Fn(...)>` type and trip `clippy::type_complexity`.
+type Comparator = Box<dyn Fn(&FileInfo, &FileInfo) -> std::cmp::Ordering>;
+
 impl DirContents {
-    fn print(&self) {
+    /// Sorts each bucket by `sort_by`, then reverses the result if `reverse` is set.
+    /// `SortBy::None` preserves directory order (aside from the optional reversal).
+    fn sort(&mut self, sort_by: SortBy, reverse: bool) {
+        let comparator: Comparator = match sort_by {
+            SortBy::Name => Box::new(|a: &FileInfo, b: &FileInfo| a.name.to_lowercase().cmp(&b.name.to_lowercase())),
+            SortBy::Size => Box::new(|a: &FileInfo, b: &FileInfo| b.size.cmp(&a.size)),
+            SortBy::Time => Box::new(|a: &FileInfo, b: &FileInfo| b.modified.cmp(&a.modified)),
+            SortBy::None => Box::new(|_a: &FileInfo, _b: &FileInfo| std::cmp::Ordering::Equal),
+        };
+
+        self.files.sort_by(|a, b| comparator(a, b));
+        self.directories.sort_by(|a, b| comparator(a, b));
+        self.executables.sort_by(|a, b| comparator(a, b));
+        self.symlinks.sort_by(|a, b| comparator(a, b));
+        self.block_devices.sort_by(|a, b| comparator(a, b));
+        self.char_devices.sort_by(|a, b| comparator(a, b));
+        self.sockets.sort_by(|a, b| comparator(a, b));
+        self.fifos.sort_by(|a, b| comparator(a, b));
+
+        if reverse {
+            self.files.reverse();
+            self.directories.reverse();
+            self.executables.reverse();
+            self.symlinks.reverse();
+            self.block_devices.reverse();
+            self.char_devices.reverse();
+            self.sockets.reverse();
+            self.fifos.reverse();
+        }
+
+        for (_, child) in &mut self.children {
+            child.sort(sort_by, reverse);
+        }
+    }
+
+    fn print(&self, options: &PrintOptions) {
+        if self.recursive {
+            self.print_tree(Path::new("."), 0, options);
+        } else {
+            self.print_block(0, options);
+        }
+    }
+
+    /// Walks the tree depth-first, printing a `relative/path:` header before every
+    /// directory block (including the root, matching `ls -R`), with a blank line before
+    /// every header except the root's.
+    fn print_tree(&self, relative_path: &Path, depth: usize, options: &PrintOptions) {
+        if depth > 0 {
+            println!();
+        }
+        println!("{}:", relative_path.display());
+        self.print_block(depth, options);
+
+        for (name, child) in &self.children {
+            child.print_tree(&relative_path.join(name), depth + 1, options);
+        }
+    }
+
+    /// Prints this directory's own files/directories/executables as an aligned table,
+    /// indented two spaces per `depth` to reflect how deep it is in a `-R` tree.
+    ///
+    /// Column widths are measured from the uncolored names via `get_longest_field_entries`,
+    /// since ANSI escape codes have zero display width but are not zero-length strings.
+    fn print_block(&self, depth: usize, options: &PrintOptions) {
+        let indent: String = "  ".repeat(depth);
         let mut tw = TabWriter::new(std::io::stdout()).padding(4);
         let max_lengths = self.get_longest_field_entries();
         let modified_at_delim: String = std::iter::repeat('-').take(max_lengths.max_date_len).collect();
         let size_of_delim: String = std::iter::repeat('-').take(max_lengths.max_size_len).collect();
         let name_of_delim: String = std::iter::repeat('-').take(max_lengths.max_name_len).collect();
-    
-        writeln!(tw, "Modified\tSize\tName").unwrap();
-        writeln!(tw, "---{}\t{}\t{}", modified_at_delim, size_of_delim, name_of_delim).unwrap();
-    
+
+        if options.long {
+            let perm_delim: String = "-".repeat(max_lengths.max_perm_len);
+            let owner_delim: String = "-".repeat(max_lengths.max_owner_len);
+            let group_delim: String = "-".repeat(max_lengths.max_group_len);
+
+            writeln!(tw, "{}Permissions\tOwner\tGroup\tModified\tSize\tName", indent).unwrap();
+            writeln!(
+                tw,
+                "{}{}\t{}\t{}\t---{}\t{}\t{}",
+                indent, perm_delim, owner_delim, group_delim, modified_at_delim, size_of_delim, name_of_delim
+            ).unwrap();
+        } else {
+            writeln!(tw, "{}Modified\tSize\tName", indent).unwrap();
+            writeln!(tw, "{}---{}\t{}\t{}", indent, modified_at_delim, size_of_delim, name_of_delim).unwrap();
+        }
+
         for entry in &self.directories {
-            writeln!(tw, "📁 {}\t{}\t{}/", entry.modified_at, entry.readable_size, entry.name).unwrap();
+            let name = options.colors.paint(&entry.name, "di");
+            if options.long {
+                writeln!(tw, "{}{}\t{}\t{}\t📁 {}\t{}\t{}/", indent, entry.permissions, entry.owner, entry.group, entry.modified_at, entry.readable_size, name).unwrap();
+            } else {
+                writeln!(tw, "{}📁 {}\t{}\t{}/", indent, entry.modified_at, entry.readable_size, name).unwrap();
+            }
         }
 
         for entry in &self.files {
-            writeln!(tw, "{} {}\t{}\t{}", get_file_emoji(&entry.name[..]), entry.modified_at, entry.readable_size, entry.name).unwrap();
+            let name = options.colors.paint(&entry.name, "fi");
+            if options.long {
+                writeln!(tw, "{}{}\t{}\t{}\t{} {}\t{}\t{}", indent, entry.permissions, entry.owner, entry.group, get_file_emoji(&entry.name[..]), entry.modified_at, entry.readable_size, name).unwrap();
+            } else {
+                writeln!(tw, "{}{} {}\t{}\t{}", indent, get_file_emoji(&entry.name[..]), entry.modified_at, entry.readable_size, name).unwrap();
+            }
         }
 
         for entry in &self.executables {
-            writeln!(tw, "⚡ {}\t{}\t{}", entry.modified_at, entry.readable_size, entry.name).unwrap();
+            let name = options.colors.paint(&entry.name, "ex");
+            if options.long {
+                writeln!(tw, "{}{}\t{}\t{}\t⚡ {}\t{}\t{}", indent, entry.permissions, entry.owner, entry.group, entry.modified_at, entry.readable_size, name).unwrap();
+            } else {
+                writeln!(tw, "{}⚡ {}\t{}\t{}", indent, entry.modified_at, entry.readable_size, name).unwrap();
+            }
+        }
+
+        for entry in &self.symlinks {
+            let name = options.colors.paint(&entry.name, "ln");
+            let name = match &entry.symlink_target {
+                Some(target) => format!("{} -> {}", name, target),
+                None => name,
+            };
+            if options.long {
+                writeln!(tw, "{}{}\t{}\t{}\t🔗 {}\t{}\t{}", indent, entry.permissions, entry.owner, entry.group, entry.modified_at, entry.readable_size, name).unwrap();
+            } else {
+                writeln!(tw, "{}🔗 {}\t{}\t{}", indent, entry.modified_at, entry.readable_size, name).unwrap();
+            }
+        }
+
+        for entry in &self.block_devices {
+            let name = options.colors.paint(&entry.name, "bd");
+            if options.long {
+                writeln!(tw, "{}{}\t{}\t{}\t💽 {}\t{}\t{}", indent, entry.permissions, entry.owner, entry.group, entry.modified_at, entry.readable_size, name).unwrap();
+            } else {
+                writeln!(tw, "{}💽 {}\t{}\t{}", indent, entry.modified_at, entry.readable_size, name).unwrap();
+            }
+        }
+
+        for entry in &self.char_devices {
+            let name = options.colors.paint(&entry.name, "cd");
+            if options.long {
+                writeln!(tw, "{}{}\t{}\t{}\t🔌 {}\t{}\t{}", indent, entry.permissions, entry.owner, entry.group, entry.modified_at, entry.readable_size, name).unwrap();
+            } else {
+                writeln!(tw, "{}🔌 {}\t{}\t{}", indent, entry.modified_at, entry.readable_size, name).unwrap();
+            }
+        }
+
+        for entry in &self.sockets {
+            let name = options.colors.paint(&entry.name, "so");
+            if options.long {
+                writeln!(tw, "{}{}\t{}\t{}\t🧦 {}\t{}\t{}", indent, entry.permissions, entry.owner, entry.group, entry.modified_at, entry.readable_size, name).unwrap();
+            } else {
+                writeln!(tw, "{}🧦 {}\t{}\t{}", indent, entry.modified_at, entry.readable_size, name).unwrap();
+            }
+        }
+
+        for entry in &self.fifos {
+            let name = options.colors.paint(&entry.name, "pi");
+            if options.long {
+                writeln!(tw, "{}{}\t{}\t{}\t🚰 {}\t{}\t{}", indent, entry.permissions, entry.owner, entry.group, entry.modified_at, entry.readable_size, name).unwrap();
+            } else {
+                writeln!(tw, "{}🚰 {}\t{}\t{}", indent, entry.modified_at, entry.readable_size, name).unwrap();
+            }
         }
         tw.flush().unwrap();
-    }   
+    }
 }
 
 
@@ -141,18 +772,26 @@ struct LongestFileInfoFields {
     max_name_len: usize,
     max_size_len: usize,
     max_date_len: usize,
+    max_perm_len: usize,
+    max_owner_len: usize,
+    max_group_len: usize,
 }
 
 impl DirContents {
+    /// Measures field widths from the raw (uncolored) values so `-l`/color columns
+    /// stay aligned regardless of what gets painted with ANSI codes at print time.
     fn get_longest_field_entries(&self) -> LongestFileInfoFields {
         let mut max_name_len: usize = 0;
         let mut max_size_len: usize = 0;
         let mut max_date_len: usize = 0;
+        let mut max_perm_len: usize = 0;
+        let mut max_owner_len: usize = 0;
+        let mut max_group_len: usize = 0;
 
         let mut update_max_lengths= |files: &Vec<FileInfo>|
             for file in files {
-                if file.name.len() > max_name_len {
-                    max_name_len = file.name.len();
+                if file.display_name().len() > max_name_len {
+                    max_name_len = file.display_name().len();
                 }
                 if file.readable_size.len() > max_size_len {
                     max_size_len = file.readable_size.len();
@@ -160,17 +799,34 @@ impl DirContents {
                 if file.modified_at.len() > max_date_len {
                     max_date_len = file.modified_at.len();
                 }
+                if file.permissions.len() > max_perm_len {
+                    max_perm_len = file.permissions.len();
+                }
+                if file.owner.len() > max_owner_len {
+                    max_owner_len = file.owner.len();
+                }
+                if file.group.len() > max_group_len {
+                    max_group_len = file.group.len();
+                }
             };
         update_max_lengths(&self.files);
         update_max_lengths(&self.directories);
         update_max_lengths(&self.executables);
+        update_max_lengths(&self.symlinks);
+        update_max_lengths(&self.block_devices);
+        update_max_lengths(&self.char_devices);
+        update_max_lengths(&self.sockets);
+        update_max_lengths(&self.fifos);
 
         LongestFileInfoFields {
             max_name_len,
             max_date_len,
-            max_size_len
+            max_size_len,
+            max_perm_len,
+            max_owner_len,
+            max_group_len,
         }
-    }   
+    }
 }
 
 
@@ -204,12 +860,12 @@ fn get_file_emoji(file_name: &str) -> &'static str {
     emoji_map.insert("jar", "📦");
     emoji_map.insert("tar", "📦");
     emoji_map.insert("pdf", "📕");
-    
+
     let extension = file_name
         .rsplit_once('.')
         .map(|(_, ext)| ext.to_lowercase())
-        .unwrap_or(String::new());    
-   
+        .unwrap_or(String::new());
+
     emoji_map.get(extension.as_str()).unwrap_or(&"📄")
 }
 
@@ -224,4 +880,301 @@ fn human_readable_size(size: u64) -> String {
     }
 
     format!("{:.2} {}", size, units[unit])
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixListener;
+
+    /// A scratch directory under the OS temp dir, unique per test via the PID, the test's
+    /// label, and a stack address, removed on drop so repeated runs don't collide or leak files.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(label: &str) -> TempDir {
+            let unique = format!("custom-ls-test-{}-{}-{:p}", std::process::id(), label, &label);
+            let path = env::temp_dir().join(unique);
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn classify_regular_directory() {
+        let dir = TempDir::new("dir");
+        let metadata = fs::symlink_metadata(dir.path()).unwrap();
+        let kind = FileKind::classify(&metadata.file_type(), metadata.permissions().mode());
+        assert_eq!(kind, FileKind::Directory);
+    }
+
+    #[test]
+    fn classify_plain_file() {
+        let dir = TempDir::new("file");
+        let file_path = dir.path().join("plain.txt");
+        fs::write(&file_path, b"hello").unwrap();
+
+        let metadata = fs::symlink_metadata(&file_path).unwrap();
+        let kind = FileKind::classify(&metadata.file_type(), metadata.permissions().mode());
+        assert_eq!(kind, FileKind::NormalFile);
+    }
+
+    #[test]
+    fn classify_executable_file() {
+        let dir = TempDir::new("exe");
+        let file_path = dir.path().join("run.sh");
+        fs::write(&file_path, b"#!/bin/sh\n").unwrap();
+        fs::set_permissions(&file_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let metadata = fs::symlink_metadata(&file_path).unwrap();
+        let kind = FileKind::classify(&metadata.file_type(), metadata.permissions().mode());
+        assert_eq!(kind, FileKind::Executable);
+    }
+
+    #[test]
+    fn classify_symlink_does_not_follow_to_target() {
+        let dir = TempDir::new("symlink");
+        let target_path = dir.path().join("target-dir");
+        fs::create_dir(&target_path).unwrap();
+        let link_path = dir.path().join("link");
+        std::os::unix::fs::symlink(&target_path, &link_path).unwrap();
+
+        // A naive `metadata()` call would follow the link and report `Directory`.
+        let metadata = fs::symlink_metadata(&link_path).unwrap();
+        let kind = FileKind::classify(&metadata.file_type(), metadata.permissions().mode());
+        assert_eq!(kind, FileKind::Symlink);
+    }
+
+    #[test]
+    fn classify_unix_socket() {
+        let dir = TempDir::new("socket");
+        let socket_path = dir.path().join("sock");
+        let _listener = UnixListener::bind(&socket_path).unwrap();
+
+        let metadata = fs::symlink_metadata(&socket_path).unwrap();
+        let kind = FileKind::classify(&metadata.file_type(), metadata.permissions().mode());
+        assert_eq!(kind, FileKind::Socket);
+    }
+
+    #[test]
+    fn format_permissions_normal_rwxr_xr_x() {
+        assert_eq!(format_permissions('-', 0o755), "-rwxr-xr-x");
+    }
+
+    #[test]
+    fn format_permissions_directory_world_writable() {
+        assert_eq!(format_permissions('d', 0o777), "drwxrwxrwx");
+    }
+
+    #[test]
+    fn format_permissions_setuid_with_owner_exec() {
+        assert_eq!(format_permissions('-', 0o4755), "-rwsr-xr-x");
+    }
+
+    #[test]
+    fn format_permissions_setgid_without_group_exec() {
+        // Setgid bit set but the group exec bit is not: uppercase `S`.
+        assert_eq!(format_permissions('-', 0o2644), "-rw-r-Sr--");
+    }
+
+    #[test]
+    fn format_permissions_sticky_with_other_exec() {
+        assert_eq!(format_permissions('d', 0o1777), "drwxrwxrwt");
+    }
+
+    #[test]
+    fn format_permissions_sticky_without_other_exec() {
+        // Sticky bit set but the other exec bit is not: uppercase `T`.
+        assert_eq!(format_permissions('d', 0o1770), "drwxrwx--T");
+    }
+
+    #[test]
+    fn is_tar_path_matches_tar_and_gzip_variants() {
+        assert!(is_tar_path(Path::new("archive.tar")));
+        assert!(is_tar_path(Path::new("archive.tar.gz")));
+        assert!(is_tar_path(Path::new("archive.tgz")));
+        assert!(is_tar_path(Path::new("ARCHIVE.TAR.GZ")));
+        assert!(!is_tar_path(Path::new("archive.zip")));
+    }
+
+    #[test]
+    fn classify_tar_entry_directory() {
+        assert_eq!(classify_tar_entry(true, 0o755), FileKind::Directory);
+    }
+
+    #[test]
+    fn classify_tar_entry_executable() {
+        assert_eq!(classify_tar_entry(false, 0o755), FileKind::Executable);
+    }
+
+    #[test]
+    fn classify_tar_entry_normal_file() {
+        assert_eq!(classify_tar_entry(false, 0o644), FileKind::NormalFile);
+    }
+
+    #[test]
+    fn build_tree_follows_symlinked_dir_and_collapses_ancestor_loop() {
+        let dir = TempDir::new("tree-symlink");
+
+        let target_path = dir.path().join("target");
+        fs::create_dir(&target_path).unwrap();
+        fs::write(target_path.join("inside.txt"), b"hi").unwrap();
+        let link_path = dir.path().join("link");
+        std::os::unix::fs::symlink(&target_path, &link_path).unwrap();
+
+        let sub_path = dir.path().join("sub");
+        fs::create_dir(&sub_path).unwrap();
+        std::os::unix::fs::symlink(dir.path(), sub_path.join("back-to-root")).unwrap();
+
+        let tree = build_tree(dir.path().to_str().unwrap(), true);
+
+        // `link` resolves to a real directory, so `-R` must descend into it.
+        let (_, link_contents) = tree.children.iter().find(|(name, _)| name == "link").unwrap();
+        assert!(link_contents.files.iter().any(|f| f.name == "inside.txt"));
+
+        // `sub/back-to-root` loops back to the already-visited root; it must not be
+        // queued again, or this call would never return.
+        let (_, sub_contents) = tree.children.iter().find(|(name, _)| name == "sub").unwrap();
+        assert!(sub_contents.children.is_empty());
+    }
+
+    #[test]
+    fn extract_tar_contents_survives_out_of_range_mtime() {
+        let dir = TempDir::new("tar-mtime");
+        let archive_path = dir.path().join("crafted.tar");
+
+        // A GNU-format header can encode an mtime via base-256 fields well past what
+        // `SystemTime`'s `Add` can represent; `set_mtime` uses that extension for values
+        // too large for the plain octal field, so this reproduces a crafted/corrupted
+        // archive without hand-rolling header bytes.
+        let mut header = tar::Header::new_gnu();
+        header.set_path("huge-mtime.txt").unwrap();
+        header.set_size(0);
+        header.set_mode(0o644);
+        header.set_mtime(u64::MAX);
+        header.set_cksum();
+
+        let file = fs::File::create(&archive_path).unwrap();
+        let mut builder = tar::Builder::new(file);
+        builder.append(&header, std::io::empty()).unwrap();
+        builder.finish().unwrap();
+
+        // Previously `UNIX_EPOCH + Duration::from_secs(mtime)` panicked here; it must
+        // instead fall back to the epoch without crashing the listing.
+        let contents = extract_tar_contents(&archive_path);
+        let entry = contents.files.iter().find(|f| f.name == "huge-mtime.txt").unwrap();
+        assert_eq!(entry.modified, UNIX_EPOCH);
+        assert_eq!(entry.modified_at, "1970-01-01 00:00:00");
+    }
+
+    fn make_cli(sort_size: bool, sort_time: bool, no_sort: bool) -> Cli {
+        Cli {
+            path: ".".to_string(),
+            sort_size,
+            sort_time,
+            no_sort,
+            reverse: false,
+            recursive: false,
+            color: "auto".to_string(),
+            long: false,
+        }
+    }
+
+    #[test]
+    fn sort_by_from_cli_prefers_no_sort_over_time_and_size() {
+        assert_eq!(SortBy::from_cli(&make_cli(true, true, true)), SortBy::None);
+    }
+
+    #[test]
+    fn sort_by_from_cli_prefers_time_over_size() {
+        assert_eq!(SortBy::from_cli(&make_cli(true, true, false)), SortBy::Time);
+    }
+
+    #[test]
+    fn sort_by_from_cli_falls_back_to_size() {
+        assert_eq!(SortBy::from_cli(&make_cli(true, false, false)), SortBy::Size);
+    }
+
+    #[test]
+    fn sort_by_from_cli_defaults_to_name() {
+        assert_eq!(SortBy::from_cli(&make_cli(false, false, false)), SortBy::Name);
+    }
+
+    fn make_file(name: &str, size: u64, modified_secs: u64) -> FileInfo {
+        FileInfo {
+            name: name.to_string(),
+            readable_size: String::new(),
+            modified_at: String::new(),
+            size,
+            modified: UNIX_EPOCH + Duration::from_secs(modified_secs),
+            permissions: String::new(),
+            owner: String::new(),
+            group: String::new(),
+            symlink_target: None,
+        }
+    }
+
+    fn make_dir_contents(files: Vec<FileInfo>) -> DirContents {
+        DirContents {
+            files,
+            directories: Vec::new(),
+            executables: Vec::new(),
+            symlinks: Vec::new(),
+            block_devices: Vec::new(),
+            char_devices: Vec::new(),
+            sockets: Vec::new(),
+            fifos: Vec::new(),
+            children: Vec::new(),
+            recursive: false,
+        }
+    }
+
+    fn names(contents: &DirContents) -> Vec<&str> {
+        contents.files.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    #[test]
+    fn sort_by_name_is_case_insensitive() {
+        let mut contents = make_dir_contents(vec![make_file("banana", 1, 1), make_file("Apple", 1, 1), make_file("cherry", 1, 1)]);
+        contents.sort(SortBy::Name, false);
+        assert_eq!(names(&contents), vec!["Apple", "banana", "cherry"]);
+    }
+
+    #[test]
+    fn sort_by_size_is_largest_first() {
+        let mut contents = make_dir_contents(vec![make_file("small", 10, 1), make_file("big", 1000, 1), make_file("medium", 100, 1)]);
+        contents.sort(SortBy::Size, false);
+        assert_eq!(names(&contents), vec!["big", "medium", "small"]);
+    }
+
+    #[test]
+    fn sort_by_time_is_newest_first() {
+        let mut contents = make_dir_contents(vec![make_file("old", 1, 100), make_file("new", 1, 300), make_file("mid", 1, 200)]);
+        contents.sort(SortBy::Time, false);
+        assert_eq!(names(&contents), vec!["new", "mid", "old"]);
+    }
+
+    #[test]
+    fn sort_none_preserves_directory_order() {
+        let mut contents = make_dir_contents(vec![make_file("c", 1, 1), make_file("a", 1, 1), make_file("b", 1, 1)]);
+        contents.sort(SortBy::None, false);
+        assert_eq!(names(&contents), vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn sort_reverse_flips_the_result() {
+        let mut contents = make_dir_contents(vec![make_file("a", 1, 1), make_file("b", 2, 1), make_file("c", 3, 1)]);
+        contents.sort(SortBy::Size, true);
+        assert_eq!(names(&contents), vec!["a", "b", "c"]);
+    }
+}